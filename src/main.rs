@@ -1,12 +1,12 @@
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine as _};
 use chrono::Utc;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use copypasta::{ClipboardContext, ClipboardProvider};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
-use magic::Cookie;
 use human_units::Size;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fmt::Debug;
 use std::fs;
@@ -15,6 +15,9 @@ use std::path::Path;
 
 const DEFAULT_BASE_URL: &str = "http://localhost:8000";
 const DEFAULT_MAX_FILE_SIZE: u64 = 4096;
+const DEFAULT_CHUNK_SIZE: u64 = 1024 * 1024;
+/// How much of a file we read from disk before handing it to the MIME sniffer.
+const SNIFF_PREFIX_LEN: usize = 8192;
 
 #[derive(Parser)]
 #[command(
@@ -32,6 +35,8 @@ Environment variables:
   BPASTE_API_BASE_URL       Override base URL
   BPASTE_API_KEY        API key (required unless provided elsewhere)
   BPASTE_MAX_FILE_SIZE  Maximum file size (e.g. 10M, 512K)
+  BPASTE_CHUNK_SIZE     Upload chunk size (e.g. 1M, 256K)
+  BPASTE_MAX_FILE_LIFE  Expire pastes after this duration (e.g. 10min, 1day)
   BPASTE_CONFIG_PATH    Explicit path to config file
 
 Config file discovery (if BPASTE_CONFIG_PATH and --config-path absent):
@@ -44,6 +49,8 @@ Supported keys:
   base_url      = https://bepasty.example.org
   api_key       = mysecretapikey
   max_file_size = 5M
+  chunk_size    = 1M
+  max_life      = 10min
 
 Example (~/.config/bpaste/bpaste.conf):
   # Bpaste uploader configuration
@@ -51,11 +58,19 @@ Example (~/.config/bpaste/bpaste.conf):
   api_key = abcdef123456
   max_file_size = 10M
 
-Units for max_file_size follow human_units crate (K, M, G etc)."#
+Units for max_file_size follow human_units crate (K, M, G etc).
+Units for --expire / max_life follow humantime duration syntax (sec, min, hours, days, weeks, months, years).
+
+Multiple files, directories, and glob patterns (e.g. bpaste ./logs/*.txt ./dir) are
+expanded and uploaded individually; pass --archive to bundle them into one tar upload
+instead.
+
+Use --format json for machine-readable output, -q/--quiet to suppress status lines,
+-v/--verbose for diagnostics on stderr, and --no-clipboard to skip the clipboard."#
 )]
 struct Args {
-    /// File to upload, or '-' for stdin
-    file: Option<String>,
+    /// File(s), directories, or glob patterns to upload, or '-' for stdin; omit for clipboard
+    file: Vec<String>,
 
     /// Override bepasty base URL
     #[arg(long)]
@@ -71,12 +86,63 @@ struct Args {
 
     #[arg(long, value_parser=clap::value_parser!(Size), help = "Maximum file size in bytes")]
     max_file_size: Option<Size>,
+
+    #[arg(long, value_parser=clap::value_parser!(Size), help = "Chunk size for uploads in bytes")]
+    chunk_size: Option<Size>,
+
+    /// Expire the paste after this duration (e.g. 10min, 2hours, 1day, 3weeks)
+    #[arg(long)]
+    expire: Option<String>,
+
+    /// Destroy the paste after its first successful retrieval
+    #[arg(long)]
+    oneshot: bool,
+
+    /// Destroy the paste after N retrievals (implies --oneshot semantics, but for N accesses)
+    #[arg(long)]
+    max_access: Option<u32>,
+
+    /// Store the argument (or clipboard content) as a short-URL item instead of a file
+    #[arg(long)]
+    url: bool,
+
+    /// Ask the server to fetch this URL itself instead of uploading local bytes
+    #[arg(long)]
+    remote: Option<String>,
+
+    /// When multiple files are given, bundle them into a single tar archive before upload
+    #[arg(long)]
+    archive: bool,
+
+    /// Print diagnostic DEBUG lines to stderr
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Suppress non-essential output (status lines, clipboard warnings)
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Output format for the upload result
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Don't copy the resulting URL(s) to the clipboard
+    #[arg(long)]
+    no_clipboard: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 struct Config {
     base_url: String,
     api_key: String,
     max_file_size: u64,
+    chunk_size: u64,
+    max_life: Option<String>,
 }
 
 impl Config {
@@ -143,7 +209,76 @@ impl Config {
             return Err(anyhow!("Maximum file size must be greater than 0"));
         }
 
-        Ok(Config { base_url, api_key, max_file_size })
+        let chunk_size = get_u64(&args.chunk_size, "BPASTE_CHUNK_SIZE", "chunk_size", DEFAULT_CHUNK_SIZE);
+        if chunk_size == 0 {
+            return Err(anyhow!("Chunk size must be greater than 0"));
+        }
+
+        let max_life = args
+            .expire
+            .clone()
+            .or_else(|| env::var("BPASTE_MAX_FILE_LIFE").ok())
+            .or_else(|| file_cfg.as_ref().and_then(|c| c.get("max_life").cloned()));
+
+        if let Some(life) = &max_life {
+            // Validate eagerly so a typo surfaces before the upload starts.
+            parse_expiry(life)?;
+        }
+
+        Ok(Config { base_url, api_key, max_file_size, chunk_size, max_life })
+    }
+}
+
+/// Parses a bepasty-style expiry duration (e.g. "10min", "2 hours", "1day") using
+/// humantime's duration syntax.
+fn parse_expiry(s: &str) -> Result<std::time::Duration> {
+    humantime::parse_duration(s).map_err(|e| anyhow!("Invalid --expire value '{}': {}", s, e))
+}
+
+/// Converts a duration into the `maxlife-unit` / `maxlife-value` pair bepasty expects.
+fn duration_to_maxlife(duration: std::time::Duration) -> (&'static str, u64) {
+    ("seconds", duration.as_secs())
+}
+
+/// Per-upload options that don't come from the layered config (CLI-only, one-shot by nature).
+struct UploadOptions {
+    oneshot: bool,
+    max_access: Option<u32>,
+    verbose: bool,
+    quiet: bool,
+    format: OutputFormat,
+}
+
+impl UploadOptions {
+    fn from_args(args: &Args) -> Result<Self> {
+        if let Some(count) = args.max_access {
+            if count == 0 {
+                return Err(anyhow!("--max-access must be greater than 0"));
+            }
+        }
+        if args.verbose && args.quiet {
+            return Err(anyhow!("--verbose and --quiet are mutually exclusive"));
+        }
+        Ok(UploadOptions {
+            oneshot: args.oneshot,
+            max_access: args.max_access,
+            verbose: args.verbose,
+            quiet: args.quiet,
+            format: args.format,
+        })
+    }
+
+    /// Prints a diagnostic line to stderr, gated behind --verbose.
+    fn debug(&self, msg: &str) {
+        if self.verbose {
+            eprintln!("DEBUG: {}", msg);
+        }
+    }
+
+    /// The number of accesses after which the item should be destroyed, if any
+    /// (--max-access wins; --oneshot alone means 1).
+    fn access_limit(&self) -> Option<u32> {
+        self.max_access.or(if self.oneshot { Some(1) } else { None })
     }
 }
 
@@ -151,14 +286,41 @@ enum InputSource {
     File(String),
     Stdin,
     Clipboard,
+    /// Store the argument (or clipboard content) as a short-URL item rather than a file.
+    /// `None` means the link text itself should come from the clipboard.
+    Url(Option<String>),
+    /// Ask the server to fetch this URL itself instead of uploading local bytes.
+    Remote(String),
+    /// One or more file/directory/glob arguments to expand and upload.
+    Files(Vec<String>),
 }
 
-fn detect_input_source(args: &Args) -> InputSource {
-    match &args.file {
-        Some(file) if file == "-" => InputSource::Stdin,
-        Some(file) => InputSource::File(file.clone()),
-        None => InputSource::Clipboard,
+fn detect_input_source(args: &Args) -> Result<InputSource> {
+    if let Some(remote_url) = &args.remote {
+        if !args.file.is_empty() {
+            return Err(anyhow!(
+                "--remote doesn't take file arguments; got unexpected argument(s): {}",
+                args.file.join(", ")
+            ));
+        }
+        return Ok(InputSource::Remote(remote_url.clone()));
+    }
+    if args.url {
+        if args.file.len() > 1 {
+            return Err(anyhow!(
+                "--url takes a single URL; got unexpected extra argument(s): {}",
+                args.file[1..].join(", ")
+            ));
+        }
+        return Ok(InputSource::Url(args.file.first().cloned()));
+    }
+    if args.file.is_empty() {
+        return Ok(InputSource::Clipboard);
     }
+    if args.file.len() == 1 && args.file[0] == "-" {
+        return Ok(InputSource::Stdin);
+    }
+    Ok(InputSource::Files(args.file.clone()))
 }
 
 enum File {
@@ -173,7 +335,18 @@ impl File {
             File::Path(path) => fs::metadata(path).map(|m| m.len() as usize).unwrap_or(0),
         }
     }
-    
+
+    /// Returns a reader over the content without materializing the whole thing in RAM.
+    fn open_reader(&self) -> Result<Box<dyn Read + Send>> {
+        match self {
+            File::Bytes(bytes) => Ok(Box::new(io::Cursor::new(bytes.clone()))),
+            File::Path(path) => {
+                let file = fs::File::open(path)
+                    .map_err(|_| anyhow!("Failed to open file '{}'", path))?;
+                Ok(Box::new(io::BufReader::new(file)))
+            }
+        }
+    }
 }
 
 impl Debug for File {
@@ -185,9 +358,18 @@ impl Debug for File {
     }
 }
 
+/// What kind of bepasty item this upload should become.
+enum ItemKind {
+    File,
+    Url,
+    /// The server fetches the content itself from this URL; no local bytes are sent.
+    Remote(String),
+}
+
 struct FileContent {
     content: File,
     filename: String,
+    kind: ItemKind,
 }
 
 fn read_input(source: &InputSource) -> Result<FileContent> {
@@ -205,7 +387,7 @@ fn read_input(source: &InputSource) -> Result<FileContent> {
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown")
                 .to_string();
-            Ok(FileContent { content: File::Path(path.clone()), filename })
+            Ok(FileContent { content: File::Path(path.clone()), filename, kind: ItemKind::File })
         }
         InputSource::Stdin => {
             let mut content = Vec::new();
@@ -215,130 +397,294 @@ fn read_input(source: &InputSource) -> Result<FileContent> {
             }
             let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
             let filename = format!("stdin-{}", timestamp);
-            Ok(FileContent { content: File::Bytes(content), filename })
+            Ok(FileContent { content: File::Bytes(content), filename, kind: ItemKind::File })
         }
         InputSource::Clipboard => {
-            let mut ctx = ClipboardContext::new()
-                .map_err(|_| anyhow!("Failed to access clipboard"))?;
-            let content = ctx
-                .get_contents()
-                .map_err(|_| anyhow!("Failed to read clipboard"))?;
-            if content.is_empty() {
-                return Err(anyhow!("Clipboard is empty"));
-            }
-            let content_bytes = content.into_bytes();
+            let content_bytes = read_clipboard_text()?;
             let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
             let filename = format!("clipboard-{}", timestamp);
-            Ok(FileContent { content: File::Bytes(content_bytes), filename })
+            Ok(FileContent { content: File::Bytes(content_bytes), filename, kind: ItemKind::File })
+        }
+        InputSource::Url(Some(text)) => {
+            Ok(FileContent { content: File::Bytes(text.clone().into_bytes()), filename: "url".to_string(), kind: ItemKind::Url })
+        }
+        InputSource::Url(None) => {
+            let content_bytes = read_clipboard_text()?;
+            Ok(FileContent { content: File::Bytes(content_bytes), filename: "url".to_string(), kind: ItemKind::Url })
+        }
+        InputSource::Remote(url) => {
+            // The server does the fetching, so there are no local bytes to read.
+            let filename = url
+                .rsplit('/')
+                .find(|segment| !segment.is_empty())
+                .unwrap_or("remote")
+                .to_string();
+            Ok(FileContent { content: File::Bytes(Vec::new()), filename, kind: ItemKind::Remote(url.clone()) })
+        }
+        InputSource::Files(_) => {
+            Err(anyhow!("Files() input must be expanded into individual File() sources before reading"))
+        }
+    }
+}
+
+/// Expands file/directory/glob arguments into a flat, deduplicated list of file paths.
+fn expand_file_paths(patterns: &[String]) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let path = Path::new(pattern);
+        if path.is_dir() {
+            collect_dir_files(path, &mut paths)?;
+        } else if pattern.contains('*') || pattern.contains('?') || pattern.contains('[') {
+            for entry in glob::glob(pattern)
+                .map_err(|e| anyhow!("Invalid glob pattern '{}': {}", pattern, e))?
+            {
+                let entry = entry.map_err(|e| anyhow!("Failed to read glob match: {}", e))?;
+                if entry.is_dir() {
+                    collect_dir_files(&entry, &mut paths)?;
+                } else {
+                    paths.push(entry.to_string_lossy().to_string());
+                }
+            }
+        } else if path.exists() {
+            paths.push(pattern.clone());
+        } else {
+            return Err(anyhow!("File '{}' not found", pattern));
+        }
+    }
+    if paths.is_empty() {
+        return Err(anyhow!("No files matched the given path(s)/pattern(s)"));
+    }
+    Ok(dedup_paths(paths))
+}
+
+/// Drops paths that resolve to the same file, keeping the first occurrence, so that
+/// overlapping args (e.g. `dir` and `dir/*.txt`) don't upload the same file twice.
+fn dedup_paths(paths: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    paths
+        .into_iter()
+        .filter(|path| {
+            let key = fs::canonicalize(path).unwrap_or_else(|_| Path::new(path).to_path_buf());
+            seen.insert(key)
+        })
+        .collect()
+}
+
+fn collect_dir_files(dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| anyhow!("Failed to read directory '{}': {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_dir_files(&path, out)?;
+        } else {
+            out.push(path.to_string_lossy().to_string());
         }
     }
+    Ok(())
+}
+
+/// Bundles several files into a single in-memory tar archive for a single upload.
+fn build_archive(paths: &[String]) -> Result<FileContent> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for path in paths {
+        builder
+            .append_path(path)
+            .map_err(|e| anyhow!("Failed to add '{}' to archive: {}", path, e))?;
+    }
+    let bytes = builder
+        .into_inner()
+        .map_err(|e| anyhow!("Failed to finalize archive: {}", e))?;
+    let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
+    let filename = format!("bpaste-archive-{}.tar", timestamp);
+    Ok(FileContent { content: File::Bytes(bytes), filename, kind: ItemKind::File })
+}
+
+fn read_clipboard_text() -> Result<Vec<u8>> {
+    let mut ctx = ClipboardContext::new()
+        .map_err(|_| anyhow!("Failed to access clipboard"))?;
+    let content = ctx
+        .get_contents()
+        .map_err(|_| anyhow!("Failed to read clipboard"))?;
+    if content.is_empty() {
+        return Err(anyhow!("Clipboard is empty"));
+    }
+    Ok(content.into_bytes())
 }
 
 struct FileType {
     mime_type: String,
 }
 
+/// Sniffs the MIME type using a pure-Rust magic-byte matcher (no libmagic/FFI dependency).
 fn detect_content_type(file: &FileContent) -> Result<FileType> {
-    // Use magic to detect MIME type
-    let cookie = Cookie::open(magic::cookie::Flags::ERROR | magic::cookie::Flags::EXTENSION)?;
-    let database = &Default::default();
-    let cookie = cookie.load(database).map_err(|_| anyhow!("Failed to load magic database"))?;
-
-    
-
     let mime_type = match &file.content {
-        File::Bytes(bytes) => {
-            // println!("DEBUG: input buffer: {:X?}", bytes);
-            cookie.buffer(bytes).map_err(|_| anyhow!("Failed to detect MIME type from bytes"))?
-        },
-        File::Path(path) => cookie.file(path).map_err(|_| anyhow!("Failed to detect MIME type from path"))?,
+        File::Bytes(bytes) => tree_magic_mini::from_u8(bytes),
+        File::Path(path) => {
+            let mut f = fs::File::open(path)
+                .map_err(|_| anyhow!("Failed to open file '{}' for type detection", path))?;
+            let mut prefix = vec![0u8; SNIFF_PREFIX_LEN];
+            let n = f.read(&mut prefix)?;
+            prefix.truncate(n);
+            tree_magic_mini::from_u8(&prefix)
+        }
     };
 
-    println!("DEBUG: Detected MIME type: {}", mime_type);
-
-    // println!("DEBUG: cookie database: ");
-    // std::io::stdout().flush()?;
-    // cookie.list(database)?;
-    // std::io::stdout().flush()?;
-
-    return Ok(FileType {
+    Ok(FileType {
         mime_type: mime_type.to_string(),
-    });
-    
+    })
+}
+
+/// Resolves a (possibly relative) `Content-Location` header against the configured base URL.
+fn resolve_item_url(base_url: &str, content_location: &str) -> String {
+    if content_location.starts_with("http://") || content_location.starts_with("https://") {
+        content_location.to_string()
+    } else {
+        format!("{}{}", base_url, content_location)
+    }
 }
 
 async fn upload_to_bepasty(
     config: &Config,
     file_content: &FileContent,
-) -> Result<String> {
-    let content_type = detect_content_type(file_content)?;
-    let content_size = file_content.content.len();
-    if content_size > config.max_file_size as usize {
+    options: &UploadOptions,
+    content_type: &FileType,
+) -> Result<UploadReport> {
+    let total = file_content.content.len();
+    if total > config.max_file_size as usize {
         return Err(anyhow!(
             "File size exceeds maximum limit of {} bytes",
             config.max_file_size
         ));
     }
-    let content_range = format!("bytes 0-{}/{}", content_size - 1, content_size);
-    let mut encoded_content: String = String::new();
-
-    // Encode content as base64
-    match &file_content.content {
-        File::Bytes(bytes) => {
-            encoded_content.clone_from(&general_purpose::STANDARD.encode(bytes));
 
-        }
-        File::Path(path) => {
-            let mut file = fs::File::open(path)
-                .map_err(|_| anyhow!("Failed to open file '{}'", path))?;
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)
-                .map_err(|_| anyhow!("Failed to read file '{}'", path))?;
-            encoded_content.clone_from(&general_purpose::STANDARD.encode(buffer));
-        }
-    }
-    
-    // Prepare headers
+    // Prepare the auth header once; it's reused across every chunk.
     let auth_string = format!("username:{}", config.api_key);
     let auth_encoded = general_purpose::STANDARD.encode(auth_string);
-    
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        AUTHORIZATION,
-        HeaderValue::from_str(&format!("Basic {}", auth_encoded))?,
-    );
-    headers.insert("Content-Range", HeaderValue::from_str(&content_range)?);
-    headers.insert("Content-Filename", HeaderValue::from_str(&file_content.filename)?);
-    headers.insert(CONTENT_TYPE, HeaderValue::from_str(&content_type.mime_type)?);
-
-    println!("Uploading {}...", file_content.filename);
+    let auth_header = HeaderValue::from_str(&format!("Basic {}", auth_encoded))?;
+
+    // Only a text-mode status line; --format json must keep stdout as a single parseable value.
+    if !options.quiet && options.format == OutputFormat::Text {
+        println!("Uploading {}...", file_content.filename);
+    }
 
     let client = reqwest::Client::new();
-    let url = format!("{}/apis/rest/items", config.base_url);
-    
-    let response = client
-        .post(&url)
-        .headers(headers)
-        .body(encoded_content)
-        .send()
-        .await?;
-    
-    if !response.status().is_success() {
-        return Err(anyhow!("Upload failed with status: {}", response.status()));
-    }
-    
-    // Extract Content-Location header
-    if let Some(content_location) = response.headers().get("content-location") {
-        let location_str = content_location.to_str()?;
-        let item_id = location_str
-            .split('/')
-            .last()
-            .ok_or_else(|| anyhow!("Invalid Content-Location header"))?;
-        let final_url = format!("{}/{}", config.base_url, item_id);
-        Ok(final_url)
-    } else {
-        Err(anyhow!("No Content-Location header found in response"))
+    let create_url = format!("{}/apis/rest/items", config.base_url);
+    let chunk_size = (config.chunk_size as usize).max(1);
+
+    let mut reader = file_content.content.open_reader()?;
+    let mut item_url: Option<String> = None;
+    let mut start = 0usize;
+
+    loop {
+        let mut buffer = vec![0u8; chunk_size];
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let n = reader.read(&mut buffer[filled..])?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        buffer.truncate(filled);
+        let end = start + filled;
+
+        // An empty body has no byte range to claim; RFC 7233 represents that as "*".
+        let content_range = if total == 0 {
+            "bytes */0".to_string()
+        } else {
+            format!("bytes {}-{}/{}", start, end.saturating_sub(1), total)
+        };
+        let encoded_chunk = general_purpose::STANDARD.encode(&buffer);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, auth_header.clone());
+        headers.insert("Content-Range", HeaderValue::from_str(&content_range)?);
+        headers.insert("Content-Transfer-Encoding", HeaderValue::from_static("base64"));
+        headers.insert("Content-Filename", HeaderValue::from_str(&file_content.filename)?);
+        headers.insert(CONTENT_TYPE, HeaderValue::from_str(&content_type.mime_type)?);
+
+        // Expiry is only meaningful when the item is created, i.e. on the first chunk.
+        if item_url.is_none() {
+            if let Some(life) = &config.max_life {
+                let (unit, value) = duration_to_maxlife(parse_expiry(life)?);
+                headers.insert("Maxlife-Unit", HeaderValue::from_static(unit));
+                headers.insert("Maxlife-Value", HeaderValue::from_str(&value.to_string())?);
+            }
+            if let Some(limit) = options.access_limit() {
+                headers.insert("One-Time-Access", HeaderValue::from_static("true"));
+                headers.insert("Max-Access", HeaderValue::from_str(&limit.to_string())?);
+            }
+            match &file_content.kind {
+                ItemKind::File => {}
+                ItemKind::Url => {
+                    headers.insert("Item-Kind", HeaderValue::from_static("url"));
+                }
+                ItemKind::Remote(url) => {
+                    headers.insert("Item-Kind", HeaderValue::from_static("remote"));
+                    headers.insert("Remote-Url", HeaderValue::from_str(url)?);
+                }
+            }
+        }
+
+        let target_url = item_url.clone().unwrap_or_else(|| create_url.clone());
+        let response = client
+            .post(&target_url)
+            .headers(headers)
+            .body(encoded_chunk)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Upload failed with status: {}", response.status()));
+        }
+
+        if item_url.is_none() {
+            let content_location = response
+                .headers()
+                .get("content-location")
+                .ok_or_else(|| anyhow!("No Content-Location header found in response"))?
+                .to_str()?;
+            item_url = Some(resolve_item_url(&config.base_url, content_location));
+        }
+
+        start = end;
+        if start >= total {
+            break;
+        }
     }
+
+    let location = item_url.ok_or_else(|| anyhow!("No Content-Location header found in response"))?;
+    let item_id = location
+        .split('/')
+        .next_back()
+        .ok_or_else(|| anyhow!("Invalid Content-Location header"))?;
+
+    Ok(UploadReport {
+        url: format!("{}/{}", config.base_url, item_id),
+        item_id: item_id.to_string(),
+        filename: file_content.filename.clone(),
+        mime_type: content_type.mime_type.clone(),
+        size: total as u64,
+        expire: config.max_life.clone(),
+        oneshot: options.oneshot,
+        max_access: options.max_access,
+    })
+}
+
+/// Machine-readable summary of a completed upload, used for `--format json`.
+#[derive(Serialize)]
+struct UploadReport {
+    url: String,
+    item_id: String,
+    filename: String,
+    mime_type: String,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire: Option<String>,
+    oneshot: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_access: Option<u32>,
 }
 
 fn copy_to_clipboard(text: &str) -> Result<()> {
@@ -397,34 +743,197 @@ fn discover_config_file() -> Option<String> {
     None
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
-    let config = Config::from_args(&args)?;
+/// Sniffs the MIME type of an already-read `FileContent` and uploads it, logging its size
+/// and detected type as debug lines (gated behind --verbose).
+async fn upload_one(
+    config: &Config,
+    options: &UploadOptions,
+    file_content: &FileContent,
+) -> Result<UploadReport> {
+    options.debug(&format!("Read {} bytes from input", file_content.content.len()));
+    let content_type = detect_content_type(file_content)?;
+    options.debug(&format!("Detected MIME type: {}", content_type.mime_type));
+    upload_to_bepasty(config, file_content, options, &content_type).await
+}
+
+async fn run(
+    args: &Args,
+    config: &Config,
+    upload_options: &UploadOptions,
+) -> Result<Vec<UploadReport>> {
+    match detect_input_source(args)? {
+        InputSource::Files(patterns) => {
+            let paths = expand_file_paths(&patterns)?;
+            if args.archive && paths.len() > 1 {
+                let file_content = build_archive(&paths)?;
+                Ok(vec![upload_one(config, upload_options, &file_content).await?])
+            } else {
+                let mut reports = Vec::with_capacity(paths.len());
+                for path in &paths {
+                    let file_content = read_input(&InputSource::File(path.clone()))?;
+                    reports.push(upload_one(config, upload_options, &file_content).await?);
+                }
+                Ok(reports)
+            }
+        }
+        source => {
+            let file_content = read_input(&source)?;
+            Ok(vec![upload_one(config, upload_options, &file_content).await?])
+        }
+    }
+}
+
+fn report_text(reports: &[UploadReport], quiet: bool, no_clipboard: bool) {
+    let urls: Vec<&str> = reports.iter().map(|r| r.url.as_str()).collect();
 
-    // println!("DEBUG: Config: {:?}", config);
-    println!("DEBUG: Max file size: {}", config.max_file_size);
-    
-    let input_source = detect_input_source(&args);
-    let file_content = read_input(&input_source)?;
-    println!("DEBUG: Read {} bytes from input", file_content.content.len());
-    // println!("DEBUG: read: \n{:?}", file_content.content);
-    detect_content_type(&file_content)?;
-    
-    match upload_to_bepasty(&config, &file_content).await {
-        Ok(url) => {
-            if let Err(e) = copy_to_clipboard(&url) {
-                eprintln!("Warning: Failed to copy to clipboard: {}", e);
+    match urls.as_slice() {
+        [url] => {
+            if no_clipboard {
+                println!("Upload successful! URL: {}", url);
+            } else if let Err(e) = copy_to_clipboard(url) {
+                if !quiet {
+                    eprintln!("Warning: Failed to copy to clipboard: {}", e);
+                }
                 println!("Upload successful! URL: {}", url);
             } else {
                 println!("Upload successful! URL copied to clipboard: {}", url);
             }
         }
+        many => {
+            if no_clipboard {
+                println!("Uploaded {} files:", many.len());
+            } else if let Err(e) = copy_to_clipboard(&many.join("\n")) {
+                if !quiet {
+                    eprintln!("Warning: Failed to copy to clipboard: {}", e);
+                }
+                println!("Uploaded {} files:", many.len());
+            } else {
+                println!("Uploaded {} files; URLs copied to clipboard:", many.len());
+            }
+            for url in many {
+                println!("{}", url);
+            }
+        }
+    }
+
+    if let Some(report) = reports.iter().find(|r| r.oneshot || r.max_access.is_some()) {
+        let limit = report.max_access.unwrap_or(1);
+        let subject = if reports.len() == 1 { "this URL is" } else { "these URLs are" };
+        let usage = if limit == 1 { "single-use".to_string() } else { format!("good for {} accesses", limit) };
+        println!(
+            "Note: {} {} and will then be destroyed -- don't burn it by testing it yourself.",
+            subject, usage
+        );
+    }
+}
+
+fn report_json(reports: &[UploadReport], no_clipboard: bool) -> Result<()> {
+    if !no_clipboard {
+        let joined = reports.iter().map(|r| r.url.as_str()).collect::<Vec<_>>().join("\n");
+        let _ = copy_to_clipboard(&joined);
+    }
+
+    let json = match reports {
+        [report] => serde_json::to_string(report)?,
+        many => serde_json::to_string(many)?,
+    };
+    println!("{}", json);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let config = Config::from_args(&args)?;
+    let upload_options = UploadOptions::from_args(&args)?;
+
+    upload_options.debug(&format!("Max file size: {}", config.max_file_size));
+
+    match run(&args, &config, &upload_options).await {
+        Ok(reports) => match args.format {
+            OutputFormat::Text => report_text(&reports, upload_options.quiet, args.no_clipboard),
+            OutputFormat::Json => report_json(&reports, args.no_clipboard)?,
+        },
         Err(e) => {
             eprintln!("Upload failed: {}", e);
             std::process::exit(1);
         }
     }
-    
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Creates a fresh, empty scratch directory under the system temp dir for a single test.
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = env::temp_dir().join(format!("bpaste-test-{}-{}-{}", std::process::id(), name, n));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(path: &Path) {
+        fs::write(path, b"test").unwrap();
+    }
+
+    #[test]
+    fn collect_dir_files_recurses_into_subdirs() {
+        let dir = scratch_dir("collect-recurse");
+        touch(&dir.join("a.txt"));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        touch(&dir.join("sub").join("b.txt"));
+
+        let mut out = Vec::new();
+        collect_dir_files(&dir, &mut out).unwrap();
+
+        assert_eq!(out.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expand_file_paths_dedupes_overlapping_dir_and_glob() {
+        let dir = scratch_dir("expand-overlap");
+        touch(&dir.join("a.txt"));
+        touch(&dir.join("b.txt"));
+
+        let dir_pattern = dir.to_string_lossy().to_string();
+        let glob_pattern = dir.join("*.txt").to_string_lossy().to_string();
+
+        let paths = expand_file_paths(&[dir_pattern, glob_pattern]).unwrap();
+
+        assert_eq!(paths.len(), 2, "overlapping dir/glob args should dedupe: {:?}", paths);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expand_file_paths_errors_when_nothing_matches() {
+        let dir = scratch_dir("expand-empty");
+        let missing = dir.join("does-not-exist.txt").to_string_lossy().to_string();
+
+        let result = expand_file_paths(&[missing]);
+
+        assert!(result.is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dedup_paths_keeps_first_occurrence_order() {
+        let dir = scratch_dir("dedup-order");
+        touch(&dir.join("a.txt"));
+        touch(&dir.join("b.txt"));
+
+        let a = dir.join("a.txt").to_string_lossy().to_string();
+        let b = dir.join("b.txt").to_string_lossy().to_string();
+
+        let deduped = dedup_paths(vec![a.clone(), b.clone(), a.clone()]);
+
+        assert_eq!(deduped, vec![a, b]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}